@@ -4,11 +4,14 @@
  */
 use pathdiff::diff_paths;
 use seq_map::SeqMap;
+use sha2::{Digest, Sha256};
 use source_map_node::{Node, Span};
+use std::cell::OnceCell;
 use std::fmt::Debug;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
+use unicode_width::UnicodeWidthChar;
 pub mod prelude;
 pub type FileId = u16;
 
@@ -58,12 +61,148 @@ pub struct SourceFileLineInfo {
 }
 
 
+/// A character that is encoded using more than one UTF-8 byte.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MultiByteChar {
+    pub pos: u32,
+    pub bytes: u8,
+}
+
+/// A character whose on-screen display width differs from one column: a tab (which
+/// expands to the next `tab_width` stop) or a wide (e.g. CJK/emoji) character.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NonNarrowChar {
+    pub pos: u32,
+    pub bytes: u8,
+    pub kind: NonNarrowKind,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NonNarrowKind {
+    Tab,
+    Wide,
+}
+
+/// The result of analyzing a file's contents: the text itself plus the line/column
+/// lookup tables derived from it.
+#[derive(Debug)]
+struct SourceAnalysis {
+    contents: String,
+    line_offsets: Box<[u32]>,
+    multibyte_chars: Box<[MultiByteChar]>,
+    non_narrow_chars: Box<[NonNarrowChar]>,
+}
+
+impl SourceAnalysis {
+    fn new(contents: String) -> io::Result<Self> {
+        let (line_offsets, multibyte_chars, non_narrow_chars) = analyze_source(&contents)?;
+        Ok(Self {
+            contents,
+            line_offsets,
+            multibyte_chars,
+            non_narrow_chars,
+        })
+    }
+}
+
+/// Walks the source once, recording the line start offsets, every multi-byte
+/// character, and every character whose display width differs from one column.
+///
+/// # Errors
+///
+/// Returns an error if `contents` is larger than `u32::MAX` bytes.
+#[allow(clippy::type_complexity)]
+fn analyze_source(
+    contents: &str,
+) -> io::Result<(Box<[u32]>, Box<[MultiByteChar]>, Box<[NonNarrowChar]>)> {
+    let too_big = |_| io::Error::new(io::ErrorKind::InvalidData, "file too large for u32 byte positions");
+
+    let mut offsets = Vec::new();
+    offsets.push(0);
+    let mut multibyte_chars = Vec::new();
+    let mut non_narrow_chars = Vec::new();
+
+    for (i, ch) in contents.char_indices() {
+        if ch == '\n' {
+            let next_line_start = u32::try_from(i + 1).map_err(too_big)?;
+            offsets.push(next_line_start);
+        }
+
+        let len = ch.len_utf8();
+        if len > 1 {
+            let pos = u32::try_from(i).map_err(too_big)?;
+            multibyte_chars.push(MultiByteChar {
+                pos,
+                bytes: len as u8,
+            });
+        }
+
+        if ch == '\t' {
+            let pos = u32::try_from(i).map_err(too_big)?;
+            non_narrow_chars.push(NonNarrowChar {
+                pos,
+                bytes: len as u8,
+                kind: NonNarrowKind::Tab,
+            });
+        } else if ch.width().unwrap_or(1) == 2 {
+            let pos = u32::try_from(i).map_err(too_big)?;
+            non_narrow_chars.push(NonNarrowChar {
+                pos,
+                bytes: len as u8,
+                kind: NonNarrowKind::Wide,
+            });
+        }
+    }
+
+    // Always add the end of file position if it's not already there
+    // (happens when file doesn't end with newline)
+    let eof_offset = u32::try_from(contents.len()).map_err(too_big)?;
+    if offsets.last().map_or(true, |&last| last != eof_offset) {
+        offsets.push(eof_offset);
+    }
+
+    Ok((
+        offsets.into_boxed_slice(),
+        multibyte_chars.into_boxed_slice(),
+        non_narrow_chars.into_boxed_slice(),
+    ))
+}
+
+/// The name under which a cached source is known: either a real path under a mount,
+/// or a synthetic name for sources with no filesystem backing (REPL input,
+/// macro-expanded text, in-memory generated code).
+#[derive(Debug, Clone)]
+pub enum FileName {
+    Real(PathBuf),
+    /// Rendered with angle brackets already applied, e.g. `<repl:1>`.
+    Virtual(String),
+}
+
+impl FileName {
+    fn virtual_name(label: &str) -> Self {
+        Self::Virtual(format!("<{label}>"))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Real(path) => path.to_str().unwrap(),
+            Self::Virtual(label) => label,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FileInfo {
     pub mount_name: String,
-    pub relative_path: PathBuf,
-    pub contents: String,
-    pub line_offsets: Box<[u16]>,
+    pub relative_path: FileName,
+    /// The content hash this file was registered with, checked against the bytes
+    /// read from disk the first time `analysis` is resolved. `None` for files whose
+    /// contents were supplied directly (e.g. via [`SourceMap::add_manual`]).
+    src_hash: Option<[u8; 32]>,
+    /// Lazily resolved and permanently memoized: a load failure (missing file or
+    /// hash mismatch) is cached as `Err` so it is never retried.
+    analysis: OnceCell<Result<SourceAnalysis, ()>>,
 }
 
 #[derive(Debug)]
@@ -144,19 +283,21 @@ impl SourceMap {
         let id = self.next_file_id;
         self.next_file_id += 1;
 
-        self.add_manual(id, mount_name, &relative_path, &contents);
+        self.add_manual(id, mount_name, &relative_path, &contents)?;
 
         Ok((id, contents))
     }
 
+    /// # Errors
+    ///
     pub fn add_to_cache(
         &mut self,
         mount_name: &str,
         relative_path: &Path,
         contents: &str,
         file_id: FileId,
-    ) {
-        self.add_manual(file_id, mount_name, relative_path, contents);
+    ) -> io::Result<()> {
+        self.add_manual(file_id, mount_name, relative_path, contents)?;
         self.file_cache
             .insert(
                 (
@@ -166,37 +307,45 @@ impl SourceMap {
                 file_id,
             )
             .unwrap();
+        Ok(())
     }
 
+    /// # Errors
+    ///
+    /// Returns an error if `contents` is too large to be addressed with `u32` byte positions.
     pub fn add_manual(
         &mut self,
         id: FileId,
         mount_name: &str,
         relative_path: &Path,
         contents: &str,
-    ) {
-        let line_offsets = Self::compute_line_offsets(contents);
+    ) -> io::Result<()> {
+        let analysis = SourceAnalysis::new(contents.to_string())?;
 
         self.cache
             .insert(
                 id,
                 FileInfo {
                     mount_name: mount_name.to_string(),
-                    relative_path: relative_path.to_path_buf(),
-                    contents: contents.to_string(),
-                    line_offsets,
+                    relative_path: FileName::Real(relative_path.to_path_buf()),
+                    src_hash: None,
+                    analysis: OnceCell::from(Ok(analysis)),
                 },
             )
             .expect("could not add file info");
+        Ok(())
     }
 
+    /// # Errors
+    ///
+    /// Returns an error if `contents` is too large to be addressed with `u32` byte positions.
     pub fn add_manual_no_id(
         &mut self,
         mount_name: &str,
         relative_path: &Path,
         contents: &str,
-    ) -> FileId {
-        let line_offsets = Self::compute_line_offsets(contents);
+    ) -> io::Result<FileId> {
+        let analysis = SourceAnalysis::new(contents.to_string())?;
         let id = self.next_file_id;
         self.next_file_id += 1;
 
@@ -205,13 +354,68 @@ impl SourceMap {
                 id,
                 FileInfo {
                     mount_name: mount_name.to_string(),
-                    relative_path: relative_path.to_path_buf(),
-                    contents: contents.to_string(),
-                    line_offsets,
+                    relative_path: FileName::Real(relative_path.to_path_buf()),
+                    src_hash: None,
+                    analysis: OnceCell::from(Ok(analysis)),
+                },
+            )
+            .expect("could not add file info");
+        Ok(id)
+    }
+
+    /// Registers a source with no filesystem backing, e.g. REPL input or
+    /// macro-expanded text. `label` is rendered in angle brackets (e.g. `<repl:1>`)
+    /// wherever the source's name would normally be shown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `contents` is too large to be addressed with `u32` byte positions.
+    pub fn add_virtual(
+        &mut self,
+        mount_name: &str,
+        label: &str,
+        contents: &str,
+    ) -> io::Result<FileId> {
+        let analysis = SourceAnalysis::new(contents.to_string())?;
+        let id = self.next_file_id;
+        self.next_file_id += 1;
+
+        self.cache
+            .insert(
+                id,
+                FileInfo {
+                    mount_name: mount_name.to_string(),
+                    relative_path: FileName::virtual_name(label),
+                    src_hash: None,
+                    analysis: OnceCell::from(Ok(analysis)),
+                },
+            )
+            .expect("could not add file info");
+        Ok(id)
+    }
+
+    /// Registers a file by mount + relative path and a previously computed content
+    /// hash, without reading it from disk. The contents are loaded and verified
+    /// against `hash` lazily, the first time a line or span is actually requested
+    /// (see [`Self::get_source_line`]/[`Self::get_span_source`]).
+    pub fn add_external(
+        &mut self,
+        id: FileId,
+        mount_name: &str,
+        relative_path: &Path,
+        hash: [u8; 32],
+    ) {
+        self.cache
+            .insert(
+                id,
+                FileInfo {
+                    mount_name: mount_name.to_string(),
+                    relative_path: FileName::Real(relative_path.to_path_buf()),
+                    src_hash: Some(hash),
+                    analysis: OnceCell::new(),
                 },
             )
             .expect("could not add file info");
-        id
     }
 
     pub fn read_file_relative(
@@ -223,8 +427,12 @@ impl SourceMap {
             .file_cache
             .get(&(mount_name.to_string(), relative_path.to_string()))
         {
-            let contents = self.cache.get(found_in_cache).unwrap().contents.clone();
-            return Ok((found_in_cache.clone(), contents));
+            let file_id = *found_in_cache;
+            let contents = self
+                .resolve_analysis(file_id)
+                .map(|analysis| analysis.contents.clone())
+                .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "source not available"))?;
+            return Ok((file_id, contents));
         }
 
         let buf = self.to_file_system_path(mount_name, relative_path)?;
@@ -245,57 +453,60 @@ impl SourceMap {
         })
     }
 
-    fn compute_line_offsets(contents: &str) -> Box<[u16]> {
-        let mut offsets = Vec::new();
-        offsets.push(0);
-
-        // Track positions of all newlines
-        for (i, &byte) in contents.as_bytes().iter().enumerate() {
-            if byte == b'\n' {
-                // Safety: new line is always encoded as single octet
-                let next_line_start = u16::try_from(i + 1).expect("too big file");
-                offsets.push(next_line_start);
-            }
-        }
+    /// Resolves the analyzed contents of `file_id`, loading and hash-verifying an
+    /// external file on its first use. Returns `None` if `file_id` is unknown or the
+    /// load has (permanently) failed.
+    fn resolve_analysis(&self, file_id: FileId) -> Option<&SourceAnalysis> {
+        let file_info = self.cache.get(&file_id)?;
+        file_info
+            .analysis
+            .get_or_init(|| self.load_external(file_info))
+            .as_ref()
+            .ok()
+    }
 
-        // Always add the end of file position if it's not already there
-        // (happens when file doesn't end with newline)
-        let eof_offset = u16::try_from(contents.len()).expect("too big file");
-        if offsets.last().map_or(true, |&last| last != eof_offset) {
-            offsets.push(eof_offset);
+    /// Loads an external file's contents from disk and checks them against `file_info.src_hash`.
+    fn load_external(&self, file_info: &FileInfo) -> Result<SourceAnalysis, ()> {
+        let hash = file_info.src_hash.ok_or(())?;
+        let FileName::Real(relative_path) = &file_info.relative_path else {
+            return Err(());
+        };
+        let relative_path = relative_path.to_str().ok_or(())?;
+        let path = self
+            .to_file_system_path(&file_info.mount_name, relative_path)
+            .map_err(|_| ())?;
+        let contents = fs::read_to_string(path).map_err(|_| ())?;
+
+        let actual_hash: [u8; 32] = Sha256::digest(contents.as_bytes()).into();
+        if actual_hash != hash {
+            return Err(());
         }
 
-        offsets.into_boxed_slice()
+        SourceAnalysis::new(contents).map_err(|_| ())
     }
 
     #[must_use]
     pub fn get_span_source(&self, file_id: FileId, offset: usize, length: usize) -> &str {
-        self.cache.get(&file_id).map_or_else(
-            || {
-                "ERROR"
-                //panic!("{}", &format!("Invalid file_id {file_id} in span"));
-            },
-            |file_info| {
-                let start = offset;
-                let end = start + length;
-                &file_info.contents[start..end]
-            },
-        )
+        self.resolve_analysis(file_id).map_or("ERROR", |analysis| {
+            let start = offset;
+            let end = start + length;
+            &analysis.contents[start..end]
+        })
     }
 
     #[must_use]
     pub fn get_source_line(&self, file_id: FileId, line_number: usize) -> Option<&str> {
-        let file_info = self.cache.get(&file_id)?;
+        let analysis = self.resolve_analysis(file_id)?;
 
         // Check if the requested line number is valid
-        if line_number == 0 || line_number >= file_info.line_offsets.len() {
+        if line_number == 0 || line_number >= analysis.line_offsets.len() {
             return None;
         }
 
-        let start_offset = file_info.line_offsets[line_number - 1] as usize;
-        let end_offset = file_info.line_offsets[line_number] as usize;
+        let start_offset = analysis.line_offsets[line_number - 1] as usize;
+        let end_offset = analysis.line_offsets[line_number] as usize;
 
-        let line = &file_info.contents[start_offset..end_offset];
+        let line = &analysis.contents[start_offset..end_offset];
 
         // Remove trailing newline if present.
         // Some files may not end with a newline.
@@ -306,40 +517,120 @@ impl SourceMap {
         }
     }
 
+    /// Returns `(0, 0)` if `file_id` is unknown or its source could not be loaded.
     #[must_use]
     pub fn get_span_location_utf8(&self, file_id: FileId, offset: usize) -> (usize, usize) {
-        let file_info = self.cache.get(&file_id).expect("Invalid file_id in span");
+        let Some(analysis) = self.resolve_analysis(file_id) else {
+            return (0, 0);
+        };
 
-        let offset = offset as u16;
+        let offset = offset as u32;
 
         // Find the line containing 'offset' via binary search.
-        let line_idx = file_info
+        let line_idx = analysis
             .line_offsets
             .binary_search(&offset)
             .unwrap_or_else(|insert_point| insert_point.saturating_sub(1));
 
         // Determine the start of the line in bytes
-        let line_start = file_info.line_offsets[line_idx] as usize;
+        let line_start = analysis.line_offsets[line_idx] as usize;
         let octet_offset = offset as usize;
 
-        // Extract the line slice from line_start to offset
-        let line_text = &file_info.contents[line_start..octet_offset];
-
-        // Count UTF-8 characters in that range, because that is what the end user sees in their editor.
-        let column_character_offset = line_text.chars().count();
+        // Every multi-byte character between line_start and offset contributes
+        // (bytes - 1) octets that don't correspond to a column, so subtract that
+        // surplus instead of re-scanning the line with `.chars().count()`.
+        let surplus = Self::multibyte_surplus(&analysis.multibyte_chars, line_start, octet_offset);
+        let column_character_offset = (octet_offset - line_start) - surplus;
 
         // Add one so it makes more sense to the end user
         (line_idx + 1, column_character_offset + 1)
     }
 
+    /// Sums the extra octets contributed by multi-byte characters in `[line_start, offset)`,
+    /// using binary search to find the relevant slice of the sorted multibyte table.
+    fn multibyte_surplus(multibyte_chars: &[MultiByteChar], line_start: usize, offset: usize) -> usize {
+        let start_idx =
+            multibyte_chars.partition_point(|c| (c.pos as usize) < line_start);
+        let end_idx = multibyte_chars.partition_point(|c| (c.pos as usize) < offset);
+
+        multibyte_chars[start_idx..end_idx]
+            .iter()
+            .map(|c| usize::from(c.bytes - 1))
+            .sum()
+    }
+
+    /// Like [`Self::get_span_location_utf8`], but returns the column an editor or
+    /// terminal would actually render the caret under: tabs expand to the next
+    /// `tab_width` stop and wide (e.g. CJK/emoji) characters count as two columns.
+    ///
+    /// Returns `(0, 0)` if `file_id` is unknown or its source could not be loaded.
+    #[must_use]
+    pub fn get_span_location_display(
+        &self,
+        file_id: FileId,
+        offset: usize,
+        tab_width: usize,
+    ) -> (usize, usize) {
+        let Some(analysis) = self.resolve_analysis(file_id) else {
+            return (0, 0);
+        };
+
+        let offset_u32 = offset as u32;
+
+        let line_idx = analysis
+            .line_offsets
+            .binary_search(&offset_u32)
+            .unwrap_or_else(|insert_point| insert_point.saturating_sub(1));
+        let line_start = analysis.line_offsets[line_idx] as usize;
+
+        let display_col = Self::display_column(
+            &analysis.multibyte_chars,
+            &analysis.non_narrow_chars,
+            line_start,
+            offset,
+            tab_width,
+        );
+
+        (line_idx + 1, display_col + 1)
+    }
+
+    /// Walks the non-narrow-char table to add up the display width of `[line_start, offset)`,
+    /// skipping ahead over the plain (one-column-per-character) runs between entries instead of
+    /// inspecting every character.
+    fn display_column(
+        multibyte_chars: &[MultiByteChar],
+        non_narrow_chars: &[NonNarrowChar],
+        line_start: usize,
+        offset: usize,
+        tab_width: usize,
+    ) -> usize {
+        let start_idx = non_narrow_chars.partition_point(|c| (c.pos as usize) < line_start);
+        let end_idx = non_narrow_chars.partition_point(|c| (c.pos as usize) < offset);
+
+        let mut display_col = 0;
+        let mut byte_pos = line_start;
+
+        for non_narrow in &non_narrow_chars[start_idx..end_idx] {
+            let char_pos = non_narrow.pos as usize;
+            let plain_bytes = char_pos - byte_pos;
+            let plain_chars = plain_bytes - Self::multibyte_surplus(multibyte_chars, byte_pos, char_pos);
+            display_col += plain_chars;
+
+            display_col += match non_narrow.kind {
+                NonNarrowKind::Tab => tab_width - (display_col % tab_width),
+                NonNarrowKind::Wide => 2,
+            };
+
+            byte_pos = char_pos + usize::from(non_narrow.bytes);
+        }
+
+        let plain_bytes = offset - byte_pos;
+        display_col + plain_bytes - Self::multibyte_surplus(multibyte_chars, byte_pos, offset)
+    }
+
     #[must_use]
     pub fn fetch_relative_filename(&self, file_id: FileId) -> &str {
-        self.cache
-            .get(&file_id)
-            .unwrap()
-            .relative_path
-            .to_str()
-            .unwrap()
+        self.cache.get(&file_id).unwrap().relative_path.as_str()
     }
 
     pub fn minimal_relative_path(target: &Path, current_dir: &Path) -> io::Result<PathBuf> {
@@ -369,11 +660,15 @@ impl SourceMap {
 
     pub fn get_relative_path_to(&self, file_id: FileId, current_dir: &Path) -> io::Result<PathBuf> {
         let file_info = self.cache.get(&file_id).unwrap();
-        let mount_path = self.mounts.get(&file_info.mount_name).unwrap();
 
-        let absolute_path = mount_path.join(&file_info.relative_path);
-
-        Self::minimal_relative_path(&absolute_path, current_dir)
+        match &file_info.relative_path {
+            FileName::Virtual(label) => Ok(PathBuf::from(label)),
+            FileName::Real(relative_path) => {
+                let mount_path = self.mounts.get(&file_info.mount_name).unwrap();
+                let absolute_path = mount_path.join(relative_path);
+                Self::minimal_relative_path(&absolute_path, current_dir)
+            }
+        }
     }
 
     pub fn get_text(&self, node: &Node) -> &str {
@@ -393,7 +688,118 @@ impl SourceMap {
             .get_relative_path_to(span.file_id, current_dir)
             .unwrap();
         let (row, col) = self.get_span_location_utf8(span.file_id, span.offset as usize);
-        let line = self.get_source_line(span.file_id, row).unwrap();
+        let line = self.get_source_line(span.file_id, row).unwrap_or("ERROR");
+
+        FileLineInfo {
+            row,
+            col,
+            line: line.to_string(),
+            relative_file_name: relative_file_name.to_str().unwrap().to_string(),
+        }
+    }
+}
+
+/// A remembered line span: on the next query for the same `file_id` and an `offset`
+/// inside `[line_start, line_end)`, the row/column can be derived directly instead of
+/// re-running the `binary_search` over `line_offsets`.
+#[derive(Debug, Clone, Copy)]
+struct CachedLine {
+    file_id: FileId,
+    line_index: usize,
+    line_start: u32,
+    line_end: u32,
+}
+
+const CACHING_VIEW_RING_SIZE: usize = 3;
+
+/// Memoizes the most recently resolved lines of a [`SourceMap`], since emitting a
+/// single diagnostic often queries many spans that land on the same few lines.
+#[derive(Debug)]
+pub struct CachingSourceMapView<'a> {
+    source_map: &'a SourceMap,
+    cached_lines: [Option<CachedLine>; CACHING_VIEW_RING_SIZE],
+}
+
+impl<'a> CachingSourceMapView<'a> {
+    #[must_use]
+    pub const fn new(source_map: &'a SourceMap) -> Self {
+        Self {
+            source_map,
+            cached_lines: [None; CACHING_VIEW_RING_SIZE],
+        }
+    }
+
+    fn find_cached(&self, file_id: FileId, offset: u32) -> Option<CachedLine> {
+        self.cached_lines
+            .iter()
+            .flatten()
+            .find(|cached| {
+                cached.file_id == file_id && offset >= cached.line_start && offset < cached.line_end
+            })
+            .copied()
+    }
+
+    /// Evicts the oldest entry (the ring is ordered oldest-to-newest) and inserts
+    /// the line spanning `line_index` as the newest entry. Does nothing if `file_id`'s
+    /// source could not be loaded.
+    fn insert_cache_entry(&mut self, file_id: FileId, line_index: usize) {
+        let Some(analysis) = self.source_map.resolve_analysis(file_id) else {
+            return;
+        };
+
+        let line_start = analysis.line_offsets[line_index];
+        let line_end = analysis
+            .line_offsets
+            .get(line_index + 1)
+            .copied()
+            .unwrap_or(line_start);
+
+        self.cached_lines.rotate_left(1);
+        self.cached_lines[CACHING_VIEW_RING_SIZE - 1] = Some(CachedLine {
+            file_id,
+            line_index,
+            line_start,
+            line_end,
+        });
+    }
+
+    /// Returns `(0, 0)` if `file_id` is unknown or its source could not be loaded.
+    #[must_use]
+    pub fn get_span_location_utf8(&mut self, file_id: FileId, offset: usize) -> (usize, usize) {
+        let octet_offset = offset as u32;
+
+        if let Some(cached) = self.find_cached(file_id, octet_offset) {
+            let Some(analysis) = self.source_map.resolve_analysis(file_id) else {
+                return (0, 0);
+            };
+            let surplus = SourceMap::multibyte_surplus(
+                &analysis.multibyte_chars,
+                cached.line_start as usize,
+                offset,
+            );
+            let column_character_offset = (offset - cached.line_start as usize) - surplus;
+            return (cached.line_index + 1, column_character_offset + 1);
+        }
+
+        let location = self.source_map.get_span_location_utf8(file_id, offset);
+        if location == (0, 0) {
+            return location;
+        }
+        self.insert_cache_entry(file_id, location.0 - 1);
+        location
+    }
+
+    #[must_use]
+    pub fn get_line(&mut self, span: &Span, current_dir: &Path) -> FileLineInfo {
+        let relative_file_name = self
+            .source_map
+            .get_relative_path_to(span.file_id, current_dir)
+            .unwrap();
+        let (row, col) = self.get_span_location_utf8(span.file_id, span.offset as usize);
+        let line = self
+            .source_map
+            .get_source_line(span.file_id, row)
+            .unwrap_or("ERROR");
 
         FileLineInfo {
             row,
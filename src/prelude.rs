@@ -3,6 +3,7 @@
  * Licensed under the MIT License. See LICENSE in the project root for license information.
  */
 pub use crate::{
-    FileId, FileInfo, FileLineInfo, KeepTrackOfSourceLine, RelativePath, SourceFileLineInfo,
-    SourceLineInfo, SourceMap, SourceMapLookup, SourceMapWrapper,
+    CachingSourceMapView, FileId, FileInfo, FileLineInfo, FileName, KeepTrackOfSourceLine,
+    MultiByteChar, NonNarrowChar, NonNarrowKind, RelativePath, SourceFileLineInfo, SourceLineInfo,
+    SourceMap, SourceMapLookup, SourceMapWrapper,
 };
\ No newline at end of file
@@ -8,9 +8,19 @@ use source_map_cache::SourceMap;
 mod tests {
     use super::*;
     use seq_map::SeqMap;
+    use sha2::{Digest, Sha256};
     use source_map_node::Span;
+    use std::fs;
     use std::path::PathBuf;
 
+    // Each test that exercises `add_external` gets its own directory so they can
+    // run in parallel without clobbering each other's files.
+    fn unique_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("source_map_cache_test_{}_{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
     // Helper function to create a source map for testing without path verification
     fn create_test_source_map() -> SourceMap {
         SourceMap {
@@ -32,7 +42,7 @@ mod tests {
             "test",
             &PathBuf::from("test.txt"),
             file_content
-        );
+        ).unwrap();
 
         assert_eq!(source_map.get_source_line(file_id, 1), Some("line 1"));
         assert_eq!(source_map.get_source_line(file_id, 2), Some("line 2"));
@@ -54,7 +64,7 @@ mod tests {
             "test",
             &PathBuf::from("no_newline.txt"),
             file_content
-        );
+        ).unwrap();
 
         assert_eq!(source_map.get_source_line(file_id, 1), Some("first line"));
         assert_eq!(source_map.get_source_line(file_id, 2), Some("second line"));
@@ -79,7 +89,7 @@ mod tests {
             "test",
             &PathBuf::from("empty.txt"),
             ""
-        );
+        ).unwrap();
 
         let single_id = 2;
         source_map.add_manual(
@@ -87,7 +97,7 @@ mod tests {
             "test",
             &PathBuf::from("single.txt"),
             "just one line"
-        );
+        ).unwrap();
 
         let newlines_id = 3;
         source_map.add_manual(
@@ -95,7 +105,7 @@ mod tests {
             "test",
             &PathBuf::from("newlines.txt"),
             "\n\n\n"
-        );
+        ).unwrap();
 
         // Test empty file
         assert_eq!(source_map.get_source_line(empty_id, 1), None);
@@ -110,4 +120,225 @@ mod tests {
         assert_eq!(source_map.get_source_line(newlines_id, 3), Some(""));
         assert_eq!(source_map.get_source_line(newlines_id, 4), None);
     }
+
+    #[test]
+    fn test_file_larger_than_u16_byte_range() {
+        let mut source_map = create_test_source_map();
+
+        // 70 KB, well past the old u16 (64 KiB) ceiling on byte positions.
+        let line = "x".repeat(100);
+        let file_content = (0..700).map(|_| line.as_str()).collect::<Vec<_>>().join("\n") + "\n";
+        assert!(file_content.len() > 65_536);
+
+        let file_id = 1;
+        source_map
+            .add_manual(file_id, "test", &PathBuf::from("large.txt"), &file_content)
+            .unwrap();
+
+        assert_eq!(source_map.get_source_line(file_id, 1), Some(line.as_str()));
+        assert_eq!(source_map.get_source_line(file_id, 700), Some(line.as_str()));
+        assert_eq!(source_map.get_source_line(file_id, 701), None);
+
+        let last_line_start = file_content.len() - line.len() - 1;
+        assert_eq!(
+            source_map.get_span_location_utf8(file_id, last_line_start),
+            (700, 1)
+        );
+
+        let other_id = source_map
+            .add_manual_no_id("test", &PathBuf::from("large_no_id.txt"), &file_content)
+            .unwrap();
+        assert_eq!(source_map.get_source_line(other_id, 1), Some(line.as_str()));
+    }
+
+    #[test]
+    fn test_multibyte_column() {
+        let mut source_map = create_test_source_map();
+
+        let file_content = "h\u{e9}llo world\n\u{1f600} second line\n";
+        let file_id = 1;
+        source_map.add_manual(
+            file_id,
+            "test",
+            &PathBuf::from("multibyte.txt"),
+            file_content
+        ).unwrap();
+
+        // "\u{e9}" is 2 bytes, so byte offset 1 is still column 2 (the char itself).
+        assert_eq!(source_map.get_span_location_utf8(file_id, 1), (1, 2));
+        // Byte offset 3 is right after "h\u{e9}" (1 + 2 bytes), i.e. column 3.
+        assert_eq!(source_map.get_span_location_utf8(file_id, 3), (1, 3));
+
+        // Second line starts after the first line's 13 bytes ("h\u{e9}llo world\n" = 1+2+10).
+        let second_line_start = file_content.find('\u{1f600}').unwrap();
+        assert_eq!(source_map.get_span_location_utf8(file_id, second_line_start), (2, 1));
+        // "\u{1f600}" is 4 bytes, so right after it is column 2.
+        let after_emoji = second_line_start + '\u{1f600}'.len_utf8();
+        assert_eq!(source_map.get_span_location_utf8(file_id, after_emoji), (2, 2));
+    }
+
+    #[test]
+    fn test_caching_source_map_view() {
+        use source_map_cache::CachingSourceMapView;
+
+        let mut source_map = create_test_source_map();
+
+        let file_content = "line 1\nline 2\nline 3\n";
+        let file_id = 1;
+        source_map.add_manual(
+            file_id,
+            "test",
+            &PathBuf::from("test.txt"),
+            file_content
+        ).unwrap();
+
+        let mut view = CachingSourceMapView::new(&source_map);
+
+        // First lookup on line 2 populates the cache ...
+        assert_eq!(view.get_span_location_utf8(file_id, 7), (2, 1));
+        // ... so a second lookup on the same line is served from the cache.
+        assert_eq!(view.get_span_location_utf8(file_id, 10), (2, 4));
+
+        // Lookups on other lines still resolve correctly, and fill the remaining cache slots.
+        assert_eq!(view.get_span_location_utf8(file_id, 0), (1, 1));
+        assert_eq!(view.get_span_location_utf8(file_id, 14), (3, 1));
+    }
+
+    #[test]
+    fn test_add_virtual() {
+        let mut source_map = create_test_source_map();
+
+        let file_id = source_map
+            .add_virtual("repl", "repl:1", "let x = 1\n")
+            .unwrap();
+
+        assert_eq!(source_map.fetch_relative_filename(file_id), "<repl:1>");
+        assert_eq!(source_map.get_source_line(file_id, 1), Some("let x = 1"));
+
+        let relative_path = source_map
+            .get_relative_path_to(file_id, &PathBuf::from("/any/current/dir"))
+            .unwrap();
+        assert_eq!(relative_path, PathBuf::from("<repl:1>"));
+    }
+
+    #[test]
+    fn test_add_external_correct_hash_loads_lazily() {
+        let dir = unique_test_dir("external_ok");
+        let file_contents = "line 1\nline 2\n";
+        fs::write(dir.join("external.txt"), file_contents).unwrap();
+
+        let mut mounts = SeqMap::new();
+        mounts.insert("test".to_string(), dir.clone()).unwrap();
+        let mut source_map = SourceMap::new(&mounts).unwrap();
+
+        let hash: [u8; 32] = Sha256::digest(file_contents.as_bytes()).into();
+        let file_id = 1;
+        source_map.add_external(file_id, "test", &PathBuf::from("external.txt"), hash);
+
+        assert_eq!(source_map.get_source_line(file_id, 1), Some("line 1"));
+        assert_eq!(source_map.get_span_source(file_id, 0, 6), "line 1");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_add_external_wrong_hash_falls_back_to_error() {
+        let dir = unique_test_dir("external_bad_hash");
+        fs::write(dir.join("external.txt"), "contents\n").unwrap();
+
+        let mut mounts = SeqMap::new();
+        mounts.insert("test".to_string(), dir.clone()).unwrap();
+        let mut source_map = SourceMap::new(&mounts).unwrap();
+
+        let wrong_hash = [0u8; 32];
+        let file_id = 1;
+        source_map.add_external(file_id, "test", &PathBuf::from("external.txt"), wrong_hash);
+
+        assert_eq!(source_map.get_source_line(file_id, 1), None);
+        assert_eq!(source_map.get_span_source(file_id, 0, 1), "ERROR");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_add_external_missing_file_failure_is_never_retried() {
+        let dir = unique_test_dir("external_missing");
+
+        let mut mounts = SeqMap::new();
+        mounts.insert("test".to_string(), dir.clone()).unwrap();
+        let mut source_map = SourceMap::new(&mounts).unwrap();
+
+        let hash = [0u8; 32];
+        let file_id = 1;
+        source_map.add_external(file_id, "test", &PathBuf::from("appears_later.txt"), hash);
+
+        assert_eq!(source_map.get_source_line(file_id, 1), None);
+
+        // The file now exists on disk, but the earlier failure is memoized forever.
+        fs::write(dir.join("appears_later.txt"), "now here\n").unwrap();
+        assert_eq!(source_map.get_source_line(file_id, 1), None);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_add_external_caches_contents_after_first_successful_load() {
+        let dir = unique_test_dir("external_cache");
+        let file_path = dir.join("external.txt");
+        let file_contents = "cached line\n";
+        fs::write(&file_path, file_contents).unwrap();
+
+        let mut mounts = SeqMap::new();
+        mounts.insert("test".to_string(), dir.clone()).unwrap();
+        let mut source_map = SourceMap::new(&mounts).unwrap();
+
+        let hash: [u8; 32] = Sha256::digest(file_contents.as_bytes()).into();
+        let file_id = 1;
+        source_map.add_external(file_id, "test", &PathBuf::from("external.txt"), hash);
+
+        assert_eq!(source_map.get_source_line(file_id, 1), Some("cached line"));
+
+        fs::remove_file(&file_path).unwrap();
+
+        // The contents were memoized on first load, so the now-deleted file
+        // doesn't cause a re-read and the cached line is still returned.
+        assert_eq!(source_map.get_source_line(file_id, 1), Some("cached line"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_display_column_tabs_and_wide_chars() {
+        let mut source_map = create_test_source_map();
+
+        // "a", tab, "b", a wide CJK character, "c", newline.
+        let file_content = "a\tb\u{4e2d}c\n";
+        let file_id = 1;
+        source_map.add_manual(
+            file_id,
+            "test",
+            &PathBuf::from("tabs.txt"),
+            file_content
+        ).unwrap();
+
+        // With tab_width 4: "a" takes column 1, the tab expands to column 4,
+        // so "b" starts at display column 4 (0-indexed) -> reported column 5.
+        let b_offset = file_content.find('b').unwrap();
+        assert_eq!(
+            source_map.get_span_location_display(file_id, b_offset, 4),
+            (1, 5)
+        );
+
+        // The UTF8 column count, by contrast, only counts characters: "a" and the tab
+        // are the 2 characters before "b", so it lands at column 3.
+        assert_eq!(source_map.get_span_location_utf8(file_id, b_offset), (1, 3));
+
+        // After "b" and the double-width CJK character, "c" sits 1 display
+        // column further along than a narrow character would put it.
+        let c_offset = file_content.find('c').unwrap();
+        assert_eq!(
+            source_map.get_span_location_display(file_id, c_offset, 4),
+            (1, 8)
+        );
+    }
 }
\ No newline at end of file